@@ -0,0 +1,41 @@
+// Compares the tiled (threadgroup-shared-memory) matmul kernel against the
+// naive kernel on 512x512 and larger matrices, where memory bandwidth rather
+// than dispatch overhead should dominate.
+use cetana::backend::mps::backend::MpsBackend;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn bench_matmul_size(c: &mut Criterion, size: usize) {
+    let backend = MpsBackend::new().expect("MPS device required");
+    let a: Vec<f32> = (0..size * size).map(|i| (i % 97) as f32).collect();
+    let b: Vec<f32> = (0..size * size).map(|i| (i % 89) as f32).collect();
+    let buffer_a = backend.create_buffer(&a).expect("Failed to create buffer A");
+    let buffer_b = backend.create_buffer(&b).expect("Failed to create buffer B");
+
+    let mut group = c.benchmark_group(format!("matmul_{size}x{size}"));
+    group.bench_function("naive", |bencher| {
+        bencher.iter(|| {
+            backend
+                .matmul(black_box(&buffer_a), black_box(&buffer_b), size, size, size)
+                .expect("matmul failed")
+        });
+    });
+    group.bench_function("tiled", |bencher| {
+        bencher.iter(|| {
+            backend
+                .matmul_tiled(black_box(&buffer_a), black_box(&buffer_b), size, size, size)
+                .expect("matmul_tiled failed")
+        });
+    });
+    group.finish();
+}
+
+fn bench_matmul_512(c: &mut Criterion) {
+    bench_matmul_size(c, 512);
+}
+
+fn bench_matmul_1024(c: &mut Criterion) {
+    bench_matmul_size(c, 1024);
+}
+
+criterion_group!(benches, bench_matmul_512, bench_matmul_1024);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+// Times repeated `matmul` calls on a backend whose pipelines are compiled once
+// in `MpsBackend::new()`: with caching, each call pays only dispatch/compute
+// cost, not JIT-compilation cost. Only the cached path is measured here.
+use cetana::backend::mps::backend::MpsBackend;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn bench_cached_matmul(c: &mut Criterion) {
+    let backend = MpsBackend::new().expect("MPS device required");
+    let (m, n, k) = (128, 128, 128);
+    let a: Vec<f32> = (0..m * n).map(|i| i as f32).collect();
+    let b: Vec<f32> = (0..n * k).map(|i| i as f32).collect();
+    let buffer_a = backend.create_buffer(&a).expect("Failed to create buffer A");
+    let buffer_b = backend.create_buffer(&b).expect("Failed to create buffer B");
+
+    c.bench_function("matmul_cached_pipeline_128", |bencher| {
+        bencher.iter(|| {
+            backend
+                .matmul(black_box(&buffer_a), black_box(&buffer_b), m, n, k)
+                .expect("matmul failed")
+        });
+    });
+}
+
+criterion_group!(benches, bench_cached_matmul);
+criterion_main!(benches);
@@ -2,23 +2,476 @@ use super::{MpsCompute, MpsDevice, MpsError};
 use crate::backend::feature::{DeviceFeatures, GPU_FEATURE_FP16, GPU_FEATURE_FP64};
 use crate::backend::{Backend, Device, DeviceType};
 use crate::MlResult;
+use half::f16;
 use metal::objc::rc::autoreleasepool;
-use metal::{Buffer, MTLResourceOptions, MTLSize};
+use metal::{Buffer, CommandQueue, ComputePipelineState, Library, MTLResourceOptions, MTLSize};
+use std::collections::HashMap;
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Element datatype used for GPU buffers and compute kernels. FP16 roughly halves
+/// memory bandwidth at the cost of precision; matmul always accumulates in FP32
+/// regardless of storage dtype.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MpsDataType {
+    F32,
+    F16,
+}
+
+/// Number of weights per quantization block, matching the llama.cpp/GGUF Q4_0/Q8_0 layout.
+pub const QBLOCK_SIZE: usize = 32;
+
+/// Q8_0 block: 32 int8 weights plus one f16 scale. Dequantizes as `scale * q`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BlockQ8_0 {
+    pub scale: f16,
+    pub weights: [i8; QBLOCK_SIZE],
+}
+
+/// Q4_0 block: 32 4-bit weights packed two-per-byte plus one f16 scale.
+/// Dequantizes as `scale * (q - 8)`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BlockQ4_0 {
+    pub scale: f16,
+    pub weights: [u8; QBLOCK_SIZE / 2],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuantFormat {
+    Q4_0,
+    Q8_0,
+}
+
+/// A block-quantized weight matrix of shape `(out_features, in_features)`, row-major,
+/// with each row split into `QBLOCK_SIZE`-element blocks (the last block in a row is
+/// zero-padded if `in_features` isn't a multiple of `QBLOCK_SIZE`).
+#[derive(Debug, Clone)]
+pub struct QTensor {
+    pub format: QuantFormat,
+    pub out_features: usize,
+    pub in_features: usize,
+    q8_blocks: Vec<BlockQ8_0>,
+    q4_blocks: Vec<BlockQ4_0>,
+}
+
+impl QTensor {
+    fn blocks_per_row(in_features: usize) -> usize {
+        (in_features + QBLOCK_SIZE - 1) / QBLOCK_SIZE
+    }
+
+    pub fn quantize_q8_0(data: &[f32], out_features: usize, in_features: usize) -> Self {
+        assert_eq!(data.len(), out_features * in_features);
+
+        let mut blocks = Vec::with_capacity(out_features * Self::blocks_per_row(in_features));
+        for row in data.chunks(in_features) {
+            for chunk in row.chunks(QBLOCK_SIZE) {
+                let amax = chunk.iter().fold(0.0f32, |acc, &v| acc.max(v.abs()));
+                let scale = if amax == 0.0 { 1.0 } else { amax / 127.0 };
+
+                let mut weights = [0i8; QBLOCK_SIZE];
+                for (i, &v) in chunk.iter().enumerate() {
+                    weights[i] = (v / scale).round().clamp(-127.0, 127.0) as i8;
+                }
+
+                blocks.push(BlockQ8_0 {
+                    scale: f16::from_f32(scale),
+                    weights,
+                });
+            }
+        }
+
+        Self {
+            format: QuantFormat::Q8_0,
+            out_features,
+            in_features,
+            q8_blocks: blocks,
+            q4_blocks: Vec::new(),
+        }
+    }
+
+    pub fn quantize_q4_0(data: &[f32], out_features: usize, in_features: usize) -> Self {
+        assert_eq!(data.len(), out_features * in_features);
+
+        let mut blocks = Vec::with_capacity(out_features * Self::blocks_per_row(in_features));
+        for row in data.chunks(in_features) {
+            for chunk in row.chunks(QBLOCK_SIZE) {
+                let amax = chunk.iter().fold(0.0f32, |acc, &v| acc.max(v.abs()));
+                let scale = if amax == 0.0 { 1.0 } else { amax / 8.0 };
+
+                let mut weights = [0u8; QBLOCK_SIZE / 2];
+                for (i, &v) in chunk.iter().enumerate() {
+                    let q = ((v / scale).round() + 8.0).clamp(0.0, 15.0) as u8;
+                    if i % 2 == 0 {
+                        weights[i / 2] = q;
+                    } else {
+                        weights[i / 2] |= q << 4;
+                    }
+                }
+
+                blocks.push(BlockQ4_0 {
+                    scale: f16::from_f32(scale),
+                    weights,
+                });
+            }
+        }
+
+        Self {
+            format: QuantFormat::Q4_0,
+            out_features,
+            in_features,
+            q8_blocks: Vec::new(),
+            q4_blocks: blocks,
+        }
+    }
+
+    /// Reconstructs the full `(out_features, in_features)` row-major f32 matrix.
+    pub fn dequantize(&self) -> Vec<f32> {
+        let blocks_per_row = Self::blocks_per_row(self.in_features);
+        let mut out = vec![0.0f32; self.out_features * self.in_features];
+
+        match self.format {
+            QuantFormat::Q8_0 => {
+                for (row, row_blocks) in self.q8_blocks.chunks(blocks_per_row).enumerate() {
+                    for (b, block) in row_blocks.iter().enumerate() {
+                        let scale = block.scale.to_f32();
+                        for (i, &w) in block.weights.iter().enumerate() {
+                            let col = b * QBLOCK_SIZE + i;
+                            if col < self.in_features {
+                                out[row * self.in_features + col] = scale * w as f32;
+                            }
+                        }
+                    }
+                }
+            }
+            QuantFormat::Q4_0 => {
+                for (row, row_blocks) in self.q4_blocks.chunks(blocks_per_row).enumerate() {
+                    for (b, block) in row_blocks.iter().enumerate() {
+                        let scale = block.scale.to_f32();
+                        for i in 0..QBLOCK_SIZE {
+                            let col = b * QBLOCK_SIZE + i;
+                            if col >= self.in_features {
+                                continue;
+                            }
+                            let byte = block.weights[i / 2];
+                            let nibble = if i % 2 == 0 { byte & 0x0F } else { byte >> 4 };
+                            out[row * self.in_features + col] = scale * (nibble as f32 - 8.0);
+                        }
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Names of the compute kernels compiled and cached at backend construction time.
+/// Each entry maps a kernel name to the `.metal` source file it lives in.
+const KERNELS: &[(&str, &str)] = &[
+    (
+        "matrix_multiply",
+        include_str!("../../../shaders/metal/matrix_ops.metal"),
+    ),
+    (
+        "vector_add",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "vector_sub",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "vector_mul",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "vector_log",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "vector_sum",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "half_matmul",
+        include_str!("../../../shaders/metal/matrix_ops.metal"),
+    ),
+    (
+        "half_add",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "half_sub",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "half_mul",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "half_log",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "half_sum",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "vector_exp",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "vector_pow",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "vector_sqrt",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "vector_div",
+        include_str!("../../../shaders/metal/binary_ops.metal"),
+    ),
+    (
+        "relu",
+        include_str!("../../../shaders/metal/activation_ops.metal"),
+    ),
+    (
+        "gelu",
+        include_str!("../../../shaders/metal/activation_ops.metal"),
+    ),
+    (
+        "silu",
+        include_str!("../../../shaders/metal/activation_ops.metal"),
+    ),
+    (
+        "softmax",
+        include_str!("../../../shaders/metal/activation_ops.metal"),
+    ),
+    (
+        "matrix_multiply_tiled",
+        include_str!("../../../shaders/metal/matrix_ops.metal"),
+    ),
+    (
+        "conv2d",
+        include_str!("../../../shaders/metal/conv_ops.metal"),
+    ),
+    (
+        "broadcast_add",
+        include_str!("../../../shaders/metal/broadcast_ops.metal"),
+    ),
+    (
+        "broadcast_div",
+        include_str!("../../../shaders/metal/broadcast_ops.metal"),
+    ),
+    (
+        "broadcast_fmax",
+        include_str!("../../../shaders/metal/broadcast_ops.metal"),
+    ),
+    (
+        "broadcast_fmin",
+        include_str!("../../../shaders/metal/broadcast_ops.metal"),
+    ),
+    (
+        "mat_mul_q8_0_f32",
+        include_str!("../../../shaders/metal/matrix_ops.metal"),
+    ),
+    (
+        "mat_mul_q4_0_f32",
+        include_str!("../../../shaders/metal/matrix_ops.metal"),
+    ),
+];
+
+/// Host-side mirror of the `Conv2dParams` struct in `conv_ops.metal`; field order
+/// and types must match exactly since it's passed to the kernel as a raw buffer.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct Conv2dParams {
+    n: u32,
+    c: u32,
+    h: u32,
+    w: u32,
+    oc: u32,
+    kh: u32,
+    kw: u32,
+    sh: u32,
+    sw: u32,
+    ph: u32,
+    pw: u32,
+    oh: u32,
+    ow: u32,
+    has_bias: u32,
+}
+
+/// Binary op supported by `broadcast_binary`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadcastOp {
+    Add,
+    Div,
+    Max,
+    Min,
+}
+
+impl BroadcastOp {
+    fn kernel_name(self) -> &'static str {
+        match self {
+            BroadcastOp::Add => "broadcast_add",
+            BroadcastOp::Div => "broadcast_div",
+            BroadcastOp::Max => "broadcast_fmax",
+            BroadcastOp::Min => "broadcast_fmin",
+        }
+    }
+}
+
+/// Host-side mirror of the `BroadcastOffsets` struct in `broadcast_ops.metal`:
+/// for each output element, the element offsets to read/write in the output,
+/// lhs, and rhs buffers respectively.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct BroadcastOffsets {
+    out_idx: u32,
+    lhs_idx: u32,
+    rhs_idx: u32,
+}
 
 #[derive(Debug)]
 pub struct MpsBackend {
     device: Arc<MpsDevice>,
     compute: MpsCompute,
+    /// Command queue shared by every op so we don't pay queue-creation cost per call.
+    command_queue: Arc<CommandQueue>,
+    /// Compute pipelines for each kernel, compiled once in `new()` and reused thereafter.
+    pipelines: HashMap<&'static str, ComputePipelineState>,
+    /// Counter sample buffer for GPU timestamp profiling, if the device supports it.
+    counter_sample_buffer: Option<metal::CounterSampleBuffer>,
+    /// Nanoseconds per GPU counter tick, measured once at startup; see
+    /// `measure_gpu_time_scale`.
+    gpu_time_scale: f64,
+    /// Duration of the most recent `*_profiled` op, read back via `last_op_timing`.
+    last_op_timing: Mutex<Option<Duration>>,
 }
 
 impl MpsBackend {
     pub fn new() -> MlResult<Self> {
         let device = Arc::new(MpsDevice::new().expect("Failed to create MPS device"));
         let compute = MpsCompute::new(Arc::clone(&device)).expect("Failed to create MPS compute");
+        let command_queue = Arc::new(device.device().new_command_queue());
+        let pipelines = Self::compile_pipelines(&device)
+            .map_err(|_| MpsError::ShaderCompilationError)
+            .expect("Failed to compile MPS kernels");
+        let counter_sample_buffer = Self::make_counter_sample_buffer(&device);
+        let gpu_time_scale = Self::measure_gpu_time_scale(&device);
+
+        Ok(Self {
+            device,
+            compute,
+            command_queue,
+            pipelines,
+            counter_sample_buffer,
+            gpu_time_scale,
+            last_op_timing: Mutex::new(None),
+        })
+    }
+
+    /// Measures nanoseconds-per-GPU-tick by sampling `MTLDevice`'s correlated
+    /// CPU/GPU timestamps twice across a short sleep and comparing how far each
+    /// clock advanced. `MTLCounterSampleBuffer` timestamps are raw GPU ticks
+    /// whose duration varies by device, not nanoseconds, so any duration derived
+    /// from them must be scaled by this factor before being reported.
+    fn measure_gpu_time_scale(device: &MpsDevice) -> f64 {
+        let (cpu_start, gpu_start) = device.device().sample_timestamps();
+        std::thread::sleep(Duration::from_millis(1));
+        let (cpu_end, gpu_end) = device.device().sample_timestamps();
+
+        let cpu_delta = cpu_end.saturating_sub(cpu_start) as f64;
+        let gpu_delta = gpu_end.saturating_sub(gpu_start) as f64;
+
+        if gpu_delta == 0.0 {
+            1.0
+        } else {
+            cpu_delta / gpu_delta
+        }
+    }
+
+    /// Sets up a 2-sample timestamp counter buffer for GPU-side profiling, if the
+    /// device exposes a "timestamp" counter set. Returns `None` on devices that
+    /// don't support timestamp sampling, in which case `*_profiled` ops silently
+    /// fall back to their unprofiled counterpart and `last_op_timing` reports `None`.
+    fn make_counter_sample_buffer(device: &MpsDevice) -> Option<metal::CounterSampleBuffer> {
+        let counter_sets = device.device().counter_sets();
+        let timestamp_set = counter_sets.iter().find(|set| set.name() == "timestamp")?;
+
+        let descriptor = metal::CounterSampleBufferDescriptor::new();
+        descriptor.set_counter_set(timestamp_set);
+        descriptor.set_storage_mode(metal::MTLStorageMode::Shared);
+        descriptor.set_sample_count(2);
+
+        device
+            .device()
+            .new_counter_sample_buffer_with_descriptor(&descriptor)
+            .ok()
+    }
+
+    /// Compiles every kernel in `KERNELS` once and returns the resulting pipeline states,
+    /// keyed by kernel name. Several kernel names share the same `.metal` source file,
+    /// so each unique source is compiled into a `Library` once and then reused to look
+    /// up every function that lives in it, rather than recompiling the source per kernel.
+    fn compile_pipelines(
+        device: &MpsDevice,
+    ) -> Result<HashMap<&'static str, ComputePipelineState>, MpsError> {
+        let mut libraries: HashMap<&'static str, Library> = HashMap::new();
+        let mut pipelines = HashMap::with_capacity(KERNELS.len());
+
+        for &(name, source) in KERNELS {
+            if !libraries.contains_key(source) {
+                let library = device
+                    .device()
+                    .new_library_with_source(source, &metal::CompileOptions::new())
+                    .map_err(|_| MpsError::ShaderCompilationError)?;
+                libraries.insert(source, library);
+            }
+            let library = &libraries[source];
+
+            let function = library
+                .get_function(name, None)
+                .map_err(|_| MpsError::ShaderCompilationError)?;
+
+            let pipeline = device
+                .device()
+                .new_compute_pipeline_state_with_function(&function)
+                .map_err(|_| MpsError::ShaderCompilationError)?;
+
+            pipelines.insert(name, pipeline);
+        }
 
-        Ok(Self { device, compute })
+        Ok(pipelines)
+    }
+
+    fn pipeline(&self, name: &str) -> Result<&ComputePipelineState, MpsError> {
+        self.pipelines
+            .get(name)
+            .ok_or(MpsError::ShaderCompilationError)
+    }
+
+    /// Duration of the most recently executed `*_profiled` op's GPU compute encoder,
+    /// resolved from GPU timestamp counters. This is pure kernel execution time and
+    /// does not include dispatch/commit latency. `None` if no profiled op has run
+    /// yet, or the device doesn't support timestamp counters.
+    pub fn last_op_timing(&self) -> Option<Duration> {
+        *self.last_op_timing.lock().unwrap()
+    }
+
+    /// Resolves the two timestamp samples captured around a profiled compute
+    /// encoder into a GPU execution duration, converting the raw tick delta to
+    /// nanoseconds via `gpu_time_scale`.
+    fn resolve_timing(&self, counter_sample_buffer: &metal::CounterSampleBuffer) -> Option<Duration> {
+        let data = counter_sample_buffer.resolve_counter_range(0..2)?;
+        let timestamps: &[u64] = unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u64, 2) };
+        let tick_delta = timestamps[1].saturating_sub(timestamps[0]) as f64;
+        Some(Duration::from_nanos((tick_delta * self.gpu_time_scale) as u64))
     }
 
     pub fn create_buffer<T: Copy>(&self, data: &[T]) -> Result<Buffer, MpsError> {
@@ -54,43 +507,1075 @@ impl MpsBackend {
         let n_buffer = self.create_buffer(&[n as u32])?;
         let k_buffer = self.create_buffer(&[k as u32])?;
 
-        let library = self
+        let pipeline = self.pipeline("matrix_multiply")?;
+
+        let thread_group_size = MTLSize::new(16, 16, 1);
+        let grid_size = MTLSize::new(
+            ((m + 15) / 16) as u64, // ceil(m / threads_per_group_x)
+            ((k + 15) / 16) as u64, // ceil(k / threads_per_group_y)
+            1,                      // Only one layer in the z-dimension
+        );
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(b), 0);
+        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&m_buffer), 0);
+        compute_encoder.set_buffer(4, Some(&n_buffer), 0);
+        compute_encoder.set_buffer(5, Some(&k_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    /// Matrix multiplication backed by Apple's tuned `MPSMatrixMultiplication` kernel.
+    ///
+    /// Computes `result = alpha * op(a) * op(b) + beta * result`, where `op` transposes
+    /// its operand when the corresponding `transpose_*` flag is set. `a` is `m x n`
+    /// (or `n x m` if `transpose_a`), `b` is `n x k` (or `k x n` if `transpose_b`), and
+    /// `result` is `m x k`. Returns an error when MPS fails to stand up the matrix
+    /// descriptors or multiplication kernel; `Backend::matmul` then falls back to
+    /// `matmul_tiled` and finally to the hand-written kernel in `matmul`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn matmul_mps(
+        &self,
+        a: &Buffer,
+        b: &Buffer,
+        m: usize,
+        n: usize,
+        k: usize,
+        transpose_a: bool,
+        transpose_b: bool,
+        alpha: f32,
+        beta: f32,
+    ) -> Result<Buffer, MpsError> {
+        use metal::mps::{Matrix, MatrixDescriptor, MatrixMultiplication};
+
+        if m == 0 || n == 0 || k == 0 {
+            return Err(MpsError::InvalidDimensions);
+        }
+
+        let f32_size = std::mem::size_of::<f32>() as u64;
+        let result_size = m * k * f32_size as usize;
+        let result_buffer = self
+            .device
+            .device()
+            .new_buffer(result_size as u64, MTLResourceOptions::StorageModeShared);
+
+        let (a_rows, a_cols) = if transpose_a { (n, m) } else { (m, n) };
+        let (b_rows, b_cols) = if transpose_b { (k, n) } else { (n, k) };
+
+        let desc_a = MatrixDescriptor::init_single(
+            a_rows as u64,
+            a_cols as u64,
+            a_cols as u64 * f32_size,
+            metal::MPSDataType::Float32,
+        );
+        let desc_b = MatrixDescriptor::init_single(
+            b_rows as u64,
+            b_cols as u64,
+            b_cols as u64 * f32_size,
+            metal::MPSDataType::Float32,
+        );
+        let desc_result = MatrixDescriptor::init_single(
+            m as u64,
+            k as u64,
+            k as u64 * f32_size,
+            metal::MPSDataType::Float32,
+        );
+
+        let matrix_a = Matrix::init_with_buffer_descriptor(a, &desc_a)
+            .ok_or(MpsError::ShaderCompilationError)?;
+        let matrix_b = Matrix::init_with_buffer_descriptor(b, &desc_b)
+            .ok_or(MpsError::ShaderCompilationError)?;
+        let matrix_result = Matrix::init_with_buffer_descriptor(&result_buffer, &desc_result)
+            .ok_or(MpsError::ShaderCompilationError)?;
+
+        let kernel = MatrixMultiplication::init(
+            self.device.device(),
+            transpose_a,
+            transpose_b,
+            m as u64,
+            k as u64,
+            n as u64,
+            alpha as f64,
+            beta as f64,
+        )
+        .ok_or(MpsError::ShaderCompilationError)?;
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        kernel.encode_to_command_buffer(command_buffer, &matrix_a, &matrix_b, &matrix_result);
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    /// FP16-storage matmul: `a` and `b` hold `f16` elements, the dot product
+    /// accumulates in `f32`, and the result is narrowed back to `f16`.
+    pub fn matmul_f16(
+        &self,
+        a: &Buffer,
+        b: &Buffer,
+        m: usize,
+        n: usize,
+        k: usize,
+    ) -> Result<Buffer, MpsError> {
+        if m == 0 || n == 0 || k == 0 {
+            return Err(MpsError::InvalidDimensions);
+        }
+
+        let result_size = m * k * std::mem::size_of::<f16>();
+        let result_buffer = self
+            .device
+            .device()
+            .new_buffer(result_size as u64, MTLResourceOptions::StorageModeShared);
+
+        let m_buffer = self.create_buffer(&[m as u32])?;
+        let n_buffer = self.create_buffer(&[n as u32])?;
+        let k_buffer = self.create_buffer(&[k as u32])?;
+
+        let pipeline = self.pipeline("half_matmul")?;
+
+        let thread_group_size = MTLSize::new(16, 16, 1);
+        let grid_size = MTLSize::new(((m + 15) / 16) as u64, ((k + 15) / 16) as u64, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(b), 0);
+        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&m_buffer), 0);
+        compute_encoder.set_buffer(4, Some(&n_buffer), 0);
+        compute_encoder.set_buffer(5, Some(&k_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    /// Blocked matmul using threadgroup shared memory to cut down on repeated
+    /// device-memory reads. Prefer this over the naive `matmul` kernel on larger
+    /// matrices where memory bandwidth, not dispatch overhead, dominates.
+    pub fn matmul_tiled(
+        &self,
+        a: &Buffer,
+        b: &Buffer,
+        m: usize,
+        n: usize,
+        k: usize,
+    ) -> Result<Buffer, MpsError> {
+        if m == 0 || n == 0 || k == 0 {
+            return Err(MpsError::InvalidDimensions);
+        }
+
+        let result_size = m * k * std::mem::size_of::<f32>();
+        let result_buffer = self
+            .device
+            .device()
+            .new_buffer(result_size as u64, MTLResourceOptions::StorageModeShared);
+
+        let m_buffer = self.create_buffer(&[m as u32])?;
+        let n_buffer = self.create_buffer(&[n as u32])?;
+        let k_buffer = self.create_buffer(&[k as u32])?;
+
+        let pipeline = self.pipeline("matrix_multiply_tiled")?;
+
+        let thread_group_size = MTLSize::new(16, 16, 1);
+        let grid_size = MTLSize::new(((m + 15) / 16) as u64, ((k + 15) / 16) as u64, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(b), 0);
+        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&m_buffer), 0);
+        compute_encoder.set_buffer(4, Some(&n_buffer), 0);
+        compute_encoder.set_buffer(5, Some(&k_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    /// Same as `matmul`, but attaches a GPU timestamp counter sample to the compute
+    /// encoder and records the resulting duration for retrieval via `last_op_timing`.
+    /// Falls back to the unprofiled naive kernel (and clears `last_op_timing` to
+    /// `None`) on devices without timestamp counter support.
+    pub fn matmul_profiled(
+        &self,
+        a: &Buffer,
+        b: &Buffer,
+        m: usize,
+        n: usize,
+        k: usize,
+    ) -> Result<Buffer, MpsError> {
+        if m == 0 || n == 0 || k == 0 {
+            return Err(MpsError::InvalidDimensions);
+        }
+
+        let Some(counter_sample_buffer) = &self.counter_sample_buffer else {
+            *self.last_op_timing.lock().unwrap() = None;
+            return self.matmul(a, b, m, n, k);
+        };
+
+        let result_size = m * k * std::mem::size_of::<f32>();
+        let result_buffer = self
             .device
             .device()
-            .new_library_with_source(
-                include_str!("../../../shaders/metal/matrix_ops.metal"),
-                &metal::CompileOptions::new(),
-            )
-            .map_err(|_| MpsError::ShaderCompilationError)?;
+            .new_buffer(result_size as u64, MTLResourceOptions::StorageModeShared);
+
+        let m_buffer = self.create_buffer(&[m as u32])?;
+        let n_buffer = self.create_buffer(&[n as u32])?;
+        let k_buffer = self.create_buffer(&[k as u32])?;
+
+        let pipeline = self.pipeline("matrix_multiply")?;
+
+        let thread_group_size = MTLSize::new(16, 16, 1);
+        let grid_size = MTLSize::new(((m + 15) / 16) as u64, ((k + 15) / 16) as u64, 1);
+
+        let pass_descriptor = metal::ComputePassDescriptor::new();
+        let sample_attachment = pass_descriptor
+            .sample_buffer_attachments()
+            .object_at(0)
+            .ok_or(MpsError::ShaderCompilationError)?;
+        sample_attachment.set_sample_buffer(counter_sample_buffer);
+        sample_attachment.set_start_of_encoder_sample_index(0);
+        sample_attachment.set_end_of_encoder_sample_index(1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder =
+            command_buffer.new_compute_command_encoder_with_descriptor(pass_descriptor);
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(b), 0);
+        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&m_buffer), 0);
+        compute_encoder.set_buffer(4, Some(&n_buffer), 0);
+        compute_encoder.set_buffer(5, Some(&k_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        *self.last_op_timing.lock().unwrap() = self.resolve_timing(counter_sample_buffer);
+
+        Ok(result_buffer)
+    }
+
+    /// Shared profiled dispatch path for the `vector_add`/`vector_sub`/`vector_mul`/
+    /// `vector_div` family, which all share the `(a, b, result, size)` buffer layout.
+    /// Same fallback behavior as `matmul_profiled`: falls back to the unprofiled
+    /// kernel and clears `last_op_timing` on devices without timestamp counters.
+    fn binary_op_profiled(
+        &self,
+        kernel_name: &str,
+        a: &Buffer,
+        b: &Buffer,
+        size: usize,
+    ) -> Result<Buffer, MpsError> {
+        let Some(counter_sample_buffer) = &self.counter_sample_buffer else {
+            *self.last_op_timing.lock().unwrap() = None;
+            return match kernel_name {
+                "vector_add" => self.add(a, b, size),
+                "vector_sub" => self.sub(a, b, size),
+                "vector_mul" => self.multiply(a, b, size),
+                _ => self.div(a, b, size),
+            };
+        };
+
+        let result_buffer = self.device.device().new_buffer(
+            (size * std::mem::size_of::<f32>()) as u64,
+            MTLResourceOptions::StorageModeShared,
+        );
+
+        let pipeline = self.pipeline(kernel_name)?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
+
+        let thread_group_size = MTLSize::new(256, 1, 1);
+        let grid_size = MTLSize::new(((size + 255) / 256) as u64, 1, 1);
+
+        let pass_descriptor = metal::ComputePassDescriptor::new();
+        let sample_attachment = pass_descriptor
+            .sample_buffer_attachments()
+            .object_at(0)
+            .ok_or(MpsError::ShaderCompilationError)?;
+        sample_attachment.set_sample_buffer(counter_sample_buffer);
+        sample_attachment.set_start_of_encoder_sample_index(0);
+        sample_attachment.set_end_of_encoder_sample_index(1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder =
+            command_buffer.new_compute_command_encoder_with_descriptor(pass_descriptor);
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(b), 0);
+        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&size_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        *self.last_op_timing.lock().unwrap() = self.resolve_timing(counter_sample_buffer);
+
+        Ok(result_buffer)
+    }
+
+    /// Profiled `add`; see `binary_op_profiled`.
+    pub fn add_profiled(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        self.binary_op_profiled("vector_add", a, b, size)
+    }
+
+    /// Profiled `sub`; see `binary_op_profiled`.
+    pub fn sub_profiled(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        self.binary_op_profiled("vector_sub", a, b, size)
+    }
+
+    /// Profiled `multiply`; see `binary_op_profiled`.
+    pub fn multiply_profiled(
+        &self,
+        a: &Buffer,
+        b: &Buffer,
+        size: usize,
+    ) -> Result<Buffer, MpsError> {
+        self.binary_op_profiled("vector_mul", a, b, size)
+    }
+
+    /// Profiled `div`; see `binary_op_profiled`.
+    pub fn div_profiled(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        self.binary_op_profiled("vector_div", a, b, size)
+    }
+
+    /// Runs `matmul` at the requested storage dtype. `F32` forwards straight to
+    /// `Backend::matmul`; `F16` narrows `a`/`b` to `f16`, runs `matmul_f16`, and
+    /// widens the result back to `f32`. This is the single entry point for
+    /// selecting the low-precision path described on `MpsDataType` without the
+    /// caller having to manage `f16` buffers directly.
+    pub fn matmul_dtype(
+        &self,
+        a: &[f32],
+        b: &[f32],
+        m: usize,
+        n: usize,
+        k: usize,
+        dtype: MpsDataType,
+    ) -> Vec<f32> {
+        match dtype {
+            MpsDataType::F32 => Backend::matmul(self, a, b, m, n, k),
+            MpsDataType::F16 => {
+                let a16: Vec<f16> = a.iter().map(|&v| f16::from_f32(v)).collect();
+                let b16: Vec<f16> = b.iter().map(|&v| f16::from_f32(v)).collect();
+
+                let mut result_vec = vec![0.0f32; m * k];
+
+                autoreleasepool(|| {
+                    let buffer_a = self.create_buffer(&a16).expect("Failed to create buffer A");
+                    let buffer_b = self.create_buffer(&b16).expect("Failed to create buffer B");
+                    let result_buffer = self
+                        .matmul_f16(&buffer_a, &buffer_b, m, n, k)
+                        .expect("Failed to multiply matrices (f16)");
+
+                    let result = result_buffer.contents();
+                    let result_slice =
+                        unsafe { std::slice::from_raw_parts(result as *const f16, m * k) };
+                    result_vec = result_slice.iter().map(|v| v.to_f32()).collect();
+                });
+
+                result_vec
+            }
+        }
+    }
+
+    /// Matmul against a block-quantized weight matrix: `a` is `m x in_features`,
+    /// `b_quant` is `(out_features, in_features)`, and the result is
+    /// `m x out_features`. The kernel walks `b_quant`'s blocks directly and
+    /// reconstructs each weight on the fly, so the quantized matrix is never
+    /// materialized as a full f32 buffer on the host or the GPU.
+    pub fn matmul_quantized(
+        &self,
+        a: &[f32],
+        b_quant: &QTensor,
+        m: usize,
+        n: usize,
+        k: usize,
+    ) -> Vec<f32> {
+        assert_eq!(n, b_quant.in_features, "n must match b_quant.in_features");
+        assert_eq!(k, b_quant.out_features, "k must match b_quant.out_features");
+        assert_eq!(a.len(), m * n, "a must be m x in_features");
+
+        let mut result_vec = vec![0.0f32; m * k];
+
+        autoreleasepool(|| {
+            let a_buffer = self.create_buffer(a).expect("Failed to create buffer A");
+            let result_buffer = self
+                .matmul_quantized_backend(&a_buffer, b_quant, m, n, k)
+                .expect("Failed to run quantized matmul");
+
+            let result = result_buffer.contents();
+            let result_slice = unsafe { std::slice::from_raw_parts(result as *const f32, m * k) };
+            result_vec = result_slice.to_vec();
+        });
+
+        result_vec
+    }
+
+    fn matmul_quantized_backend(
+        &self,
+        a: &Buffer,
+        b_quant: &QTensor,
+        m: usize,
+        n: usize,
+        k: usize,
+    ) -> Result<Buffer, MpsError> {
+        let (kernel_name, blocks_buffer) = match b_quant.format {
+            QuantFormat::Q8_0 => (
+                "mat_mul_q8_0_f32",
+                self.create_buffer(&b_quant.q8_blocks)?,
+            ),
+            QuantFormat::Q4_0 => (
+                "mat_mul_q4_0_f32",
+                self.create_buffer(&b_quant.q4_blocks)?,
+            ),
+        };
+
+        let result_size = m * k * std::mem::size_of::<f32>();
+        let result_buffer = self
+            .device
+            .device()
+            .new_buffer(result_size as u64, MTLResourceOptions::StorageModeShared);
+
+        let m_buffer = self.create_buffer(&[m as u32])?;
+        let n_buffer = self.create_buffer(&[n as u32])?;
+        let k_buffer = self.create_buffer(&[k as u32])?;
+
+        let pipeline = self.pipeline(kernel_name)?;
+
+        let thread_group_size = MTLSize::new(16, 16, 1);
+        let grid_size = MTLSize::new(((m + 15) / 16) as u64, ((k + 15) / 16) as u64, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(&blocks_buffer), 0);
+        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&m_buffer), 0);
+        compute_encoder.set_buffer(4, Some(&n_buffer), 0);
+        compute_encoder.set_buffer(5, Some(&k_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    /// Direct NCHW 2D convolution. `input` is `in_shape = (N, C, H, W)` and `weight`
+    /// is `(out_channels, C, kh, kw)`, both row-major f32. Returns the `(N,
+    /// out_channels, out_h, out_w)` output, where `out_h`/`out_w` follow the usual
+    /// `(dim + 2*pad - kernel) / stride + 1` formula.
+    #[allow(clippy::too_many_arguments)]
+    pub fn conv2d(
+        &self,
+        input: &[f32],
+        weight: &[f32],
+        bias: Option<&[f32]>,
+        in_shape: (usize, usize, usize, usize),
+        out_channels: usize,
+        kernel: (usize, usize),
+        stride: (usize, usize),
+        padding: (usize, usize),
+    ) -> Vec<f32> {
+        let (n, c, h, w) = in_shape;
+        let (kh, kw) = kernel;
+        let (sh, sw) = stride;
+        let (ph, pw) = padding;
+
+        let oh = (h + 2 * ph - kh) / sh + 1;
+        let ow = (w + 2 * pw - kw) / sw + 1;
+
+        let mut result_vec = vec![0.0f32; n * out_channels * oh * ow];
+
+        autoreleasepool(|| {
+            let input_buffer = self
+                .create_buffer(input)
+                .expect("Failed to create input buffer");
+            let weight_buffer = self
+                .create_buffer(weight)
+                .expect("Failed to create weight buffer");
+
+            let owned_bias;
+            let bias_data: &[f32] = match bias {
+                Some(b) => b,
+                None => {
+                    owned_bias = vec![0.0f32; out_channels];
+                    &owned_bias
+                }
+            };
+            let bias_buffer = self
+                .create_buffer(bias_data)
+                .expect("Failed to create bias buffer");
+
+            let params = Conv2dParams {
+                n: n as u32,
+                c: c as u32,
+                h: h as u32,
+                w: w as u32,
+                oc: out_channels as u32,
+                kh: kh as u32,
+                kw: kw as u32,
+                sh: sh as u32,
+                sw: sw as u32,
+                ph: ph as u32,
+                pw: pw as u32,
+                oh: oh as u32,
+                ow: ow as u32,
+                has_bias: bias.is_some() as u32,
+            };
+            let params_buffer = self
+                .create_buffer(&[params])
+                .expect("Failed to create params buffer");
+
+            let result_buffer = self
+                .conv2d_backend(
+                    &input_buffer,
+                    &weight_buffer,
+                    &bias_buffer,
+                    &params_buffer,
+                    n,
+                    out_channels,
+                    oh,
+                    ow,
+                )
+                .expect("Failed to run conv2d");
+
+            let result = result_buffer.contents();
+            let result_slice = unsafe {
+                std::slice::from_raw_parts(result as *const f32, n * out_channels * oh * ow)
+            };
+            result_vec = result_slice.to_vec();
+        });
+
+        result_vec
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn conv2d_backend(
+        &self,
+        input: &Buffer,
+        weight: &Buffer,
+        bias: &Buffer,
+        params: &Buffer,
+        n: usize,
+        oc: usize,
+        oh: usize,
+        ow: usize,
+    ) -> Result<Buffer, MpsError> {
+        let result_size = n * oc * oh * ow * std::mem::size_of::<f32>();
+        let result_buffer = self
+            .device
+            .device()
+            .new_buffer(result_size as u64, MTLResourceOptions::StorageModeShared);
+
+        let pipeline = self.pipeline("conv2d")?;
+
+        let thread_group_size = MTLSize::new(8, 8, 1);
+        let grid_size = MTLSize::new(((ow + 7) / 8) as u64, ((oh + 7) / 8) as u64, (n * oc) as u64);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(input), 0);
+        compute_encoder.set_buffer(1, Some(weight), 0);
+        compute_encoder.set_buffer(2, Some(bias), 0);
+        compute_encoder.set_buffer(3, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(4, Some(params), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    /// NumPy/PyTorch-style broadcast shape: dimensions are compared right-aligned,
+    /// and must either match or be `1` in one of the two operands.
+    fn broadcast_shape(a_shape: &[usize], b_shape: &[usize]) -> Result<Vec<usize>, MpsError> {
+        let rank = a_shape.len().max(b_shape.len());
+        let mut shape = vec![1usize; rank];
+
+        for i in 0..rank {
+            let a_dim = a_shape
+                .len()
+                .checked_sub(1 + i)
+                .map(|idx| a_shape[idx])
+                .unwrap_or(1);
+            let b_dim = b_shape
+                .len()
+                .checked_sub(1 + i)
+                .map(|idx| b_shape[idx])
+                .unwrap_or(1);
+
+            if a_dim != b_dim && a_dim != 1 && b_dim != 1 {
+                return Err(MpsError::InvalidDimensions);
+            }
+
+            shape[rank - 1 - i] = a_dim.max(b_dim);
+        }
+
+        Ok(shape)
+    }
+
+    /// Per-output-dimension strides (in elements) for one broadcast operand,
+    /// right-aligned to `rank`: `0` for dimensions where the operand's own size is
+    /// `1` (every output element along that axis reads the same value), otherwise
+    /// the operand's real row-major stride.
+    fn broadcast_strides(shape: &[usize], rank: usize) -> Vec<usize> {
+        let mut real_strides = vec![0usize; shape.len()];
+        let mut acc = 1;
+        for i in (0..shape.len()).rev() {
+            real_strides[i] = acc;
+            acc *= shape[i];
+        }
+
+        let offset = rank - shape.len();
+        let mut strides = vec![0usize; rank];
+        for i in 0..shape.len() {
+            if shape[i] != 1 {
+                strides[offset + i] = real_strides[i];
+            }
+        }
+
+        strides
+    }
+
+    /// Broadcasting binary op (`fmax`, `fmin`, `div`, bias-style `add`, ...): `a`
+    /// and `b` don't need matching shapes/strides as long as they're
+    /// NumPy-broadcastable. The host precomputes, for every output element, the
+    /// element offsets into `a` and `b` (repeating an operand's offset wherever a
+    /// dimension is being broadcast) and hands them to the GPU as an offsets table,
+    /// following the approach used by PyTorch's MPS binary kernels.
+    pub fn broadcast_binary(
+        &self,
+        a: &[f32],
+        a_shape: &[usize],
+        b: &[f32],
+        b_shape: &[usize],
+        op: BroadcastOp,
+    ) -> Result<Vec<f32>, MpsError> {
+        let out_shape = Self::broadcast_shape(a_shape, b_shape)?;
+        let rank = out_shape.len();
+        let out_len: usize = out_shape.iter().product();
+
+        let a_strides = Self::broadcast_strides(a_shape, rank);
+        let b_strides = Self::broadcast_strides(b_shape, rank);
+
+        let mut offsets = Vec::with_capacity(out_len);
+        let mut coords = vec![0usize; rank];
+        for out_idx in 0..out_len {
+            let mut rem = out_idx;
+            for d in (0..rank).rev() {
+                coords[d] = rem % out_shape[d];
+                rem /= out_shape[d];
+            }
+
+            let a_idx: usize = coords.iter().zip(&a_strides).map(|(&c, &s)| c * s).sum();
+            let b_idx: usize = coords.iter().zip(&b_strides).map(|(&c, &s)| c * s).sum();
+
+            offsets.push(BroadcastOffsets {
+                out_idx: out_idx as u32,
+                lhs_idx: a_idx as u32,
+                rhs_idx: b_idx as u32,
+            });
+        }
+
+        let mut result_vec = vec![0.0f32; out_len];
+
+        autoreleasepool(|| {
+            let a_buffer = self.create_buffer(a).expect("Failed to create buffer A");
+            let b_buffer = self.create_buffer(b).expect("Failed to create buffer B");
+            let offsets_buffer = self
+                .create_buffer(&offsets)
+                .expect("Failed to create offsets buffer");
+
+            let result_buffer = self
+                .broadcast_binary_backend(op, &a_buffer, &b_buffer, &offsets_buffer, out_len)
+                .expect("Failed to run broadcast binary op");
+
+            let result = result_buffer.contents();
+            let result_slice =
+                unsafe { std::slice::from_raw_parts(result as *const f32, out_len) };
+            result_vec = result_slice.to_vec();
+        });
+
+        Ok(result_vec)
+    }
+
+    fn broadcast_binary_backend(
+        &self,
+        op: BroadcastOp,
+        a: &Buffer,
+        b: &Buffer,
+        offsets: &Buffer,
+        out_len: usize,
+    ) -> Result<Buffer, MpsError> {
+        let result_buffer = self.device.device().new_buffer(
+            (out_len * std::mem::size_of::<f32>()) as u64,
+            MTLResourceOptions::StorageModeShared,
+        );
+
+        let pipeline = self.pipeline(op.kernel_name())?;
+        let out_len_buffer = self.create_buffer(&[out_len as u32])?;
+
+        let thread_group_size = MTLSize::new(256, 1, 1);
+        let grid_size = MTLSize::new(((out_len + 255) / 256) as u64, 1, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(b), 0);
+        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(offsets), 0);
+        compute_encoder.set_buffer(4, Some(&out_len_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    pub fn add(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        let result_buffer = self.device.device().new_buffer(
+            (size * size_of::<f32>()) as u64,
+            MTLResourceOptions::StorageModeShared,
+        );
+
+        let pipeline = self.pipeline("vector_add")?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
+
+        // Configure thread groups
+        let thread_group_size = MTLSize::new(256, 1, 1);
+        let grid_size = MTLSize::new(((size + 255) / 256) as u64, 1, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(b), 0);
+        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&size_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    /// Shared dispatch path for the FP16 `half_add`/`half_sub`/`half_mul` kernels,
+    /// which all share the add/sub/multiply buffer layout.
+    fn binary_op_f16(
+        &self,
+        kernel_name: &str,
+        a: &Buffer,
+        b: &Buffer,
+        size: usize,
+    ) -> Result<Buffer, MpsError> {
+        let result_buffer = self.device.device().new_buffer(
+            (size * std::mem::size_of::<f16>()) as u64,
+            MTLResourceOptions::StorageModeShared,
+        );
+
+        let pipeline = self.pipeline(kernel_name)?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
+
+        let thread_group_size = MTLSize::new(256, 1, 1);
+        let grid_size = MTLSize::new(((size + 255) / 256) as u64, 1, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(b), 0);
+        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&size_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    pub fn add_f16(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        self.binary_op_f16("half_add", a, b, size)
+    }
+
+    /// Runs `add` at the requested storage dtype; see `matmul_dtype`.
+    pub fn add_dtype(&self, a: &[f32], b: &[f32], dtype: MpsDataType) -> Vec<f32> {
+        match dtype {
+            MpsDataType::F32 => Backend::add(self, a, b),
+            MpsDataType::F16 => {
+                let a16: Vec<f16> = a.iter().map(|&v| f16::from_f32(v)).collect();
+                let b16: Vec<f16> = b.iter().map(|&v| f16::from_f32(v)).collect();
+
+                let mut result_vec = vec![0.0f32; a.len()];
+
+                autoreleasepool(|| {
+                    let buffer_a = self.create_buffer(&a16).expect("Failed to create buffer A");
+                    let buffer_b = self.create_buffer(&b16).expect("Failed to create buffer B");
+                    let result_buffer = self
+                        .add_f16(&buffer_a, &buffer_b, a.len())
+                        .expect("Failed to add buffers (f16)");
+
+                    let result = result_buffer.contents();
+                    let result_slice =
+                        unsafe { std::slice::from_raw_parts(result as *const f16, a.len()) };
+                    result_vec = result_slice.iter().map(|v| v.to_f32()).collect();
+                });
+
+                result_vec
+            }
+        }
+    }
+
+    pub fn sub_f16(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        self.binary_op_f16("half_sub", a, b, size)
+    }
+
+    pub fn multiply_f16(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        self.binary_op_f16("half_mul", a, b, size)
+    }
+
+    pub fn log_f16(&self, a: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        let result_buffer = self.device.device().new_buffer(
+            (size * std::mem::size_of::<f16>()) as u64,
+            MTLResourceOptions::StorageModeShared,
+        );
+
+        let pipeline = self.pipeline("half_log")?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
+
+        let thread_group_size = MTLSize::new(256, 1, 1);
+        let grid_size = MTLSize::new(((size + 255) / 256) as u64, 1, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(2, Some(&size_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    pub fn sum_backend_f16(&self, input: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        let result_buffer = self.device.device().new_buffer(
+            (size * std::mem::size_of::<f16>()) as u64,
+            MTLResourceOptions::StorageModeShared,
+        );
+
+        let pipeline = self.pipeline("half_sum")?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
+        let thread_group_size = MTLSize::new(1, 1, 1);
+        let grid_size = MTLSize::new(1, 1, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(input), 0);
+        compute_encoder.set_buffer(1, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(2, Some(&size_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    pub fn sub(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        let result_buffer = self.device.device().new_buffer(
+            (size * std::mem::size_of::<f32>()) as u64,
+            MTLResourceOptions::StorageModeShared,
+        );
+
+        let pipeline = self.pipeline("vector_sub")?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
+
+        // Configure thread groups
+        let thread_group_size = MTLSize::new(256, 1, 1);
+        let grid_size = MTLSize::new(((size + 255) / 256) as u64, 1, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(b), 0);
+        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&size_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    pub fn multiply(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        let result_buffer = self.device.device().new_buffer(
+            (size * std::mem::size_of::<f32>()) as u64,
+            MTLResourceOptions::StorageModeShared,
+        );
+
+        let pipeline = self.pipeline("vector_mul")?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
+
+        let thread_group_size = MTLSize::new(256, 1, 1);
+        let grid_size = MTLSize::new(((size + 255) / 256) as u64, 1, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(b), 0);
+        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&size_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    pub fn log(&self, a: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        let result_buffer = self.device.device().new_buffer(
+            (size * std::mem::size_of::<f32>()) as u64,
+            MTLResourceOptions::StorageModeShared,
+        );
+
+        let pipeline = self.pipeline("vector_log")?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
+
+        // Configure thread groups
+        let thread_group_size = MTLSize::new(256, 1, 1);
+        let grid_size = MTLSize::new(((size + 255) / 256) as u64, 1, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
+        let compute_encoder = command_buffer.new_compute_command_encoder();
+
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
+        compute_encoder.set_buffer(1, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(2, Some(&size_buffer), 0);
+
+        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
+        compute_encoder.end_encoding();
+
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+
+        Ok(result_buffer)
+    }
+
+    pub fn get_supported_features(&self) -> DeviceFeatures {
+        let mut features = DeviceFeatures::new();
 
-        let kernel = library
-            .get_function("matrix_multiply", None)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
+        // Check MPS-specific features
+        features.add_feature(
+            GPU_FEATURE_FP16,
+            true, // MPS supports FP16
+            Some("Half-precision floating point support".to_string()),
+        );
 
-        let pipeline = self
-            .device
-            .device()
-            .new_compute_pipeline_state_with_function(&kernel)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
+        features.add_feature(
+            GPU_FEATURE_FP64,
+            false, // MPS typically doesn't support FP64
+            Some("Double-precision floating point support".to_string()),
+        );
 
-        let thread_group_size = MTLSize::new(16, 16, 1);
-        let grid_size = MTLSize::new(
-            (m + 16) as u64, // ceil(m / threads_per_group_x)
-            (k + 16) as u64, // ceil(n / threads_per_group_y)
-            1,               // Only one layer in the z-dimension
+        features
+    }
+
+    pub fn sum_backend(&self, input: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        let result_buffer = self.device.device().new_buffer(
+            (size * std::mem::size_of::<f32>()) as u64,
+            MTLResourceOptions::StorageModeShared,
         );
 
-        let command_queue = self.device.device().new_command_queue();
-        let command_buffer = command_queue.new_command_buffer();
+        let pipeline = self.pipeline("vector_sum")?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
+        let thread_group_size = MTLSize::new(1, 1, 1);
+        let grid_size = MTLSize::new(1, 1, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
         let compute_encoder = command_buffer.new_compute_command_encoder();
 
-        compute_encoder.set_compute_pipeline_state(&pipeline);
-        compute_encoder.set_buffer(0, Some(a), 0);
-        compute_encoder.set_buffer(1, Some(b), 0);
-        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
-        compute_encoder.set_buffer(3, Some(&m_buffer), 0);
-        compute_encoder.set_buffer(4, Some(&n_buffer), 0);
-        compute_encoder.set_buffer(5, Some(&k_buffer), 0);
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(input), 0);
+        compute_encoder.set_buffer(1, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(2, Some(&size_buffer), 0);
 
         compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
         compute_encoder.end_encoding();
@@ -101,44 +1586,32 @@ impl MpsBackend {
         Ok(result_buffer)
     }
 
-    pub fn add(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+    /// Shared dispatch path for single-input, single-output kernels (`relu`, `gelu`,
+    /// `silu`, `vector_exp`, `vector_sqrt`) that all share the log/exp buffer layout.
+    fn dispatch_unary(
+        &self,
+        kernel_name: &str,
+        a: &Buffer,
+        size: usize,
+    ) -> Result<Buffer, MpsError> {
         let result_buffer = self.device.device().new_buffer(
-            (size * size_of::<f32>()) as u64,
+            (size * std::mem::size_of::<f32>()) as u64,
             MTLResourceOptions::StorageModeShared,
         );
 
-        // Create and compile the addition kernel
-        let library = self
-            .device
-            .device()
-            .new_library_with_source(
-                include_str!("../../../shaders/metal/binary_ops.metal"),
-                &metal::CompileOptions::new(),
-            )
-            .map_err(|_| MpsError::ShaderCompilationError)?;
-
-        let kernel = library
-            .get_function("vector_add", None)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
-
-        let pipeline = self
-            .device
-            .device()
-            .new_compute_pipeline_state_with_function(&kernel)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
+        let pipeline = self.pipeline(kernel_name)?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
 
-        // Configure thread groups
         let thread_group_size = MTLSize::new(256, 1, 1);
         let grid_size = MTLSize::new(((size + 255) / 256) as u64, 1, 1);
 
-        let command_queue = self.device.device().new_command_queue();
-        let command_buffer = command_queue.new_command_buffer();
+        let command_buffer = self.command_queue.new_command_buffer();
         let compute_encoder = command_buffer.new_compute_command_encoder();
 
-        compute_encoder.set_compute_pipeline_state(&pipeline);
+        compute_encoder.set_compute_pipeline_state(pipeline);
         compute_encoder.set_buffer(0, Some(a), 0);
-        compute_encoder.set_buffer(1, Some(b), 0);
-        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(1, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(2, Some(&size_buffer), 0);
 
         compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
         compute_encoder.end_encoding();
@@ -149,44 +1622,35 @@ impl MpsBackend {
         Ok(result_buffer)
     }
 
-    pub fn sub(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+    pub fn exp_backend(&self, a: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        self.dispatch_unary("vector_exp", a, size)
+    }
+
+    pub fn sqrt_backend(&self, a: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+        self.dispatch_unary("vector_sqrt", a, size)
+    }
+
+    pub fn pow_backend(&self, a: &Buffer, power: f32, size: usize) -> Result<Buffer, MpsError> {
         let result_buffer = self.device.device().new_buffer(
             (size * std::mem::size_of::<f32>()) as u64,
             MTLResourceOptions::StorageModeShared,
         );
 
-        // Create and compile the addition kernel
-        let library = self
-            .device
-            .device()
-            .new_library_with_source(
-                include_str!("../../../shaders/metal/binary_ops.metal"),
-                &metal::CompileOptions::new(),
-            )
-            .map_err(|_| MpsError::ShaderCompilationError)?;
-
-        let kernel = library
-            .get_function("vector_sub", None)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
-
-        let pipeline = self
-            .device
-            .device()
-            .new_compute_pipeline_state_with_function(&kernel)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
+        let pipeline = self.pipeline("vector_pow")?;
+        let power_buffer = self.create_buffer(&[power])?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
 
-        // Configure thread groups
         let thread_group_size = MTLSize::new(256, 1, 1);
         let grid_size = MTLSize::new(((size + 255) / 256) as u64, 1, 1);
 
-        let command_queue = self.device.device().new_command_queue();
-        let command_buffer = command_queue.new_command_buffer();
+        let command_buffer = self.command_queue.new_command_buffer();
         let compute_encoder = command_buffer.new_compute_command_encoder();
 
-        compute_encoder.set_compute_pipeline_state(&pipeline);
+        compute_encoder.set_compute_pipeline_state(pipeline);
         compute_encoder.set_buffer(0, Some(a), 0);
-        compute_encoder.set_buffer(1, Some(b), 0);
-        compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(1, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(2, Some(&power_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&size_buffer), 0);
 
         compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
         compute_encoder.end_encoding();
@@ -197,42 +1661,26 @@ impl MpsBackend {
         Ok(result_buffer)
     }
 
-    pub fn multiply(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+    pub fn div(&self, a: &Buffer, b: &Buffer, size: usize) -> Result<Buffer, MpsError> {
         let result_buffer = self.device.device().new_buffer(
             (size * std::mem::size_of::<f32>()) as u64,
             MTLResourceOptions::StorageModeShared,
         );
 
-        let library = self
-            .device
-            .device()
-            .new_library_with_source(
-                include_str!("../../../shaders/metal/binary_ops.metal"),
-                &metal::CompileOptions::new(),
-            )
-            .map_err(|_| MpsError::ShaderCompilationError)?;
-
-        let kernel = library
-            .get_function("vector_mul", None)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
-
-        let pipeline = self
-            .device
-            .device()
-            .new_compute_pipeline_state_with_function(&kernel)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
+        let pipeline = self.pipeline("vector_div")?;
+        let size_buffer = self.create_buffer(&[size as u32])?;
 
         let thread_group_size = MTLSize::new(256, 1, 1);
         let grid_size = MTLSize::new(((size + 255) / 256) as u64, 1, 1);
 
-        let command_queue = self.device.device().new_command_queue();
-        let command_buffer = command_queue.new_command_buffer();
+        let command_buffer = self.command_queue.new_command_buffer();
         let compute_encoder = command_buffer.new_compute_command_encoder();
 
-        compute_encoder.set_compute_pipeline_state(&pipeline);
+        compute_encoder.set_compute_pipeline_state(pipeline);
         compute_encoder.set_buffer(0, Some(a), 0);
         compute_encoder.set_buffer(1, Some(b), 0);
         compute_encoder.set_buffer(2, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&size_buffer), 0);
 
         compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
         compute_encoder.end_encoding();
@@ -243,105 +1691,101 @@ impl MpsBackend {
         Ok(result_buffer)
     }
 
-    pub fn log(&self, a: &Buffer, size: usize) -> Result<Buffer, MpsError> {
-        let result_buffer = self.device.device().new_buffer(
-            (size * std::mem::size_of::<f32>()) as u64,
-            MTLResourceOptions::StorageModeShared,
-        );
+    /// Rectified linear unit: `max(x, 0)`.
+    pub fn relu(&self, a: &[f32]) -> Vec<f32> {
+        let mut result_vec: Vec<f32> = vec![];
 
-        // Create and compile the addition kernel
-        let library = self
-            .device
-            .device()
-            .new_library_with_source(
-                include_str!("../../../shaders/metal/binary_ops.metal"),
-                &metal::CompileOptions::new(),
-            )
-            .map_err(|_| MpsError::ShaderCompilationError)?;
+        autoreleasepool(|| {
+            let buffer_a = self.create_buffer(a).expect("Failed to create buffer A");
+            let result_buffer = self
+                .dispatch_unary("relu", &buffer_a, a.len())
+                .expect("Failed to run relu");
 
-        let kernel = library
-            .get_function("vector_log", None)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
+            let result = result_buffer.contents();
+            let result_slice = unsafe { std::slice::from_raw_parts(result as *const f32, a.len()) };
+            result_vec = result_slice.to_vec();
+        });
 
-        let pipeline = self
-            .device
-            .device()
-            .new_compute_pipeline_state_with_function(&kernel)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
+        result_vec
+    }
 
-        // Configure thread groups
-        let thread_group_size = MTLSize::new(256, 1, 1);
-        let grid_size = MTLSize::new(((size + 255) / 256) as u64, 1, 1);
+    /// GELU (tanh approximation).
+    pub fn gelu(&self, a: &[f32]) -> Vec<f32> {
+        let mut result_vec: Vec<f32> = vec![];
 
-        let command_queue = self.device.device().new_command_queue();
-        let command_buffer = command_queue.new_command_buffer();
-        let compute_encoder = command_buffer.new_compute_command_encoder();
+        autoreleasepool(|| {
+            let buffer_a = self.create_buffer(a).expect("Failed to create buffer A");
+            let result_buffer = self
+                .dispatch_unary("gelu", &buffer_a, a.len())
+                .expect("Failed to run gelu");
 
-        compute_encoder.set_compute_pipeline_state(&pipeline);
-        compute_encoder.set_buffer(0, Some(a), 0);
-        compute_encoder.set_buffer(1, Some(&result_buffer), 0);
+            let result = result_buffer.contents();
+            let result_slice = unsafe { std::slice::from_raw_parts(result as *const f32, a.len()) };
+            result_vec = result_slice.to_vec();
+        });
 
-        compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
-        compute_encoder.end_encoding();
+        result_vec
+    }
 
-        command_buffer.commit();
-        command_buffer.wait_until_completed();
+    /// SiLU / swish: `x * sigmoid(x)`.
+    pub fn silu(&self, a: &[f32]) -> Vec<f32> {
+        let mut result_vec: Vec<f32> = vec![];
 
-        Ok(result_buffer)
+        autoreleasepool(|| {
+            let buffer_a = self.create_buffer(a).expect("Failed to create buffer A");
+            let result_buffer = self
+                .dispatch_unary("silu", &buffer_a, a.len())
+                .expect("Failed to run silu");
+
+            let result = result_buffer.contents();
+            let result_slice = unsafe { std::slice::from_raw_parts(result as *const f32, a.len()) };
+            result_vec = result_slice.to_vec();
+        });
+
+        result_vec
     }
 
-    pub fn get_supported_features(&self) -> DeviceFeatures {
-        let mut features = DeviceFeatures::new();
+    /// Numerically-stable row-wise softmax over an `[rows, cols]` matrix stored
+    /// in row-major order.
+    pub fn softmax(&self, a: &[f32], rows: usize, cols: usize) -> Vec<f32> {
+        let mut result_vec: Vec<f32> = vec![];
 
-        // Check MPS-specific features
-        features.add_feature(
-            GPU_FEATURE_FP16,
-            true, // MPS supports FP16
-            Some("Half-precision floating point support".to_string()),
-        );
+        autoreleasepool(|| {
+            let buffer_a = self.create_buffer(a).expect("Failed to create buffer A");
+            let result_buffer = self
+                .softmax_backend(&buffer_a, rows, cols)
+                .expect("Failed to run softmax");
 
-        features.add_feature(
-            GPU_FEATURE_FP64,
-            false, // MPS typically doesn't support FP64
-            Some("Double-precision floating point support".to_string()),
-        );
+            let result = result_buffer.contents();
+            let result_slice =
+                unsafe { std::slice::from_raw_parts(result as *const f32, rows * cols) };
+            result_vec = result_slice.to_vec();
+        });
 
-        features
+        result_vec
     }
 
-    pub fn sum_backend(&self, input: &Buffer, size: usize) -> Result<Buffer, MpsError> {
+    fn softmax_backend(&self, a: &Buffer, rows: usize, cols: usize) -> Result<Buffer, MpsError> {
         let result_buffer = self.device.device().new_buffer(
-            (size * std::mem::size_of::<f32>()) as u64,
+            (rows * cols * std::mem::size_of::<f32>()) as u64,
             MTLResourceOptions::StorageModeShared,
         );
 
-        let library = self
-            .device
-            .device()
-            .new_library_with_source(
-                include_str!("../../../shaders/metal/binary_ops.metal"),
-                &metal::CompileOptions::new(),
-            )
-            .map_err(|_| MpsError::ShaderCompilationError)?;
-
-        let kernel = library
-            .get_function("vector_sum", None)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
-        let pipeline = self
-            .device
-            .device()
-            .new_compute_pipeline_state_with_function(&kernel)
-            .map_err(|_| MpsError::ShaderCompilationError)?;
-        let thread_group_size = MTLSize::new(1, 1, 1);
-        let grid_size = MTLSize::new(1, 1, 1);
+        let pipeline = self.pipeline("softmax")?;
+        let rows_buffer = self.create_buffer(&[rows as u32])?;
+        let cols_buffer = self.create_buffer(&[cols as u32])?;
 
-        let command_queue = self.device.device().new_command_queue();
-        let command_buffer = command_queue.new_command_buffer();
+        let thread_group_size = MTLSize::new(256, 1, 1);
+        let grid_size = MTLSize::new(((rows + 255) / 256) as u64, 1, 1);
+
+        let command_buffer = self.command_queue.new_command_buffer();
         let compute_encoder = command_buffer.new_compute_command_encoder();
 
-        compute_encoder.set_compute_pipeline_state(&pipeline);
-        compute_encoder.set_buffer(0, Some(input), 0);
+        compute_encoder.set_compute_pipeline_state(pipeline);
+        compute_encoder.set_buffer(0, Some(a), 0);
         compute_encoder.set_buffer(1, Some(&result_buffer), 0);
+        compute_encoder.set_buffer(2, Some(&rows_buffer), 0);
+        compute_encoder.set_buffer(3, Some(&cols_buffer), 0);
 
         compute_encoder.dispatch_thread_groups(grid_size, thread_group_size);
         compute_encoder.end_encoding();
@@ -424,14 +1868,19 @@ impl Backend for MpsBackend {
             let buffer_a = self.create_buffer(a).expect("Failed to create buffer A");
             let buffer_b = self.create_buffer(b).expect("Failed to create buffer B");
 
-            // Perform matrix multiplication on Apple MPS
+            // Perform matrix multiplication on Apple MPS: prefer the tuned
+            // MPSMatrixMultiplication kernel, fall back to the tiled kernel (which
+            // reuses threadgroup memory and so beats the naive kernel on bandwidth),
+            // and fall back further to the naive kernel as a last resort.
             let result_buffer = self
-                .matmul(&buffer_a, &buffer_b, m, n, k)
+                .matmul_mps(&buffer_a, &buffer_b, m, n, k, false, false, 1.0, 0.0)
+                .or_else(|_| self.matmul_tiled(&buffer_a, &buffer_b, m, n, k))
+                .or_else(|_| self.matmul(&buffer_a, &buffer_b, m, n, k))
                 .expect("Failed to multiply matrices");
 
             // Read result buffer
             let result = result_buffer.contents();
-            let result_slice = unsafe { std::slice::from_raw_parts(result as *const f32, (m * k)) };
+            let result_slice = unsafe { std::slice::from_raw_parts(result as *const f32, m * k) };
 
             // Copy result to a Vec
             result_vec = result_slice.to_vec();
@@ -450,7 +1899,7 @@ impl Backend for MpsBackend {
 
             // Perform division on Apple MPS
             let result_buffer = self
-                .add(&buffer_a, &buffer_b, a.len())
+                .div(&buffer_a, &buffer_b, a.len())
                 .expect("Failed to divide buffers");
 
             // Read result buffer
@@ -489,7 +1938,26 @@ impl Backend for MpsBackend {
     }
 
     fn exp(&self, a: &[f32]) -> Vec<f32> {
-        todo!()
+        let mut result_vec: Vec<f32> = vec![];
+
+        autoreleasepool(|| {
+            // Create Buffers on Apple MPS
+            let buffer_a = self.create_buffer(a).expect("Failed to create buffer A");
+
+            // Perform exp on Apple MPS
+            let result_buffer = self
+                .exp_backend(&buffer_a, a.len())
+                .expect("Failed to exponentiate buffer");
+
+            // Read result buffer
+            let result = result_buffer.contents();
+            let result_slice = unsafe { std::slice::from_raw_parts(result as *const f32, a.len()) };
+
+            // Copy result to a Vec
+            result_vec = result_slice.to_vec();
+        });
+
+        result_vec
     }
 
     fn log(&self, a: &[f32]) -> Vec<f32> {
@@ -514,11 +1982,49 @@ impl Backend for MpsBackend {
     }
 
     fn pow(&self, a: &[f32], power: f32) -> Vec<f32> {
-        todo!()
+        let mut result_vec: Vec<f32> = vec![];
+
+        autoreleasepool(|| {
+            // Create Buffers on Apple MPS
+            let buffer_a = self.create_buffer(a).expect("Failed to create buffer A");
+
+            // Perform pow on Apple MPS
+            let result_buffer = self
+                .pow_backend(&buffer_a, power, a.len())
+                .expect("Failed to raise buffer to power");
+
+            // Read result buffer
+            let result = result_buffer.contents();
+            let result_slice = unsafe { std::slice::from_raw_parts(result as *const f32, a.len()) };
+
+            // Copy result to a Vec
+            result_vec = result_slice.to_vec();
+        });
+
+        result_vec
     }
 
     fn sqrt(&self, a: &[f32]) -> Vec<f32> {
-        todo!()
+        let mut result_vec: Vec<f32> = vec![];
+
+        autoreleasepool(|| {
+            // Create Buffers on Apple MPS
+            let buffer_a = self.create_buffer(a).expect("Failed to create buffer A");
+
+            // Perform sqrt on Apple MPS
+            let result_buffer = self
+                .sqrt_backend(&buffer_a, a.len())
+                .expect("Failed to take square root of buffer");
+
+            // Read result buffer
+            let result = result_buffer.contents();
+            let result_slice = unsafe { std::slice::from_raw_parts(result as *const f32, a.len()) };
+
+            // Copy result to a Vec
+            result_vec = result_slice.to_vec();
+        });
+
+        result_vec
     }
 
     fn sum(&self, a: &[f32]) -> f32 {
@@ -570,3 +2076,317 @@ impl Device for MpsBackend {
         self.get_supported_features()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f32], expected: &[f32], tol: f32) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a - e).abs() <= tol, "{a} vs {e} exceeds tolerance {tol}");
+        }
+    }
+
+    fn cpu_matmul(a: &[f32], b: &[f32], m: usize, n: usize, k: usize) -> Vec<f32> {
+        let mut out = vec![0.0f32; m * k];
+        for i in 0..m {
+            for j in 0..k {
+                let mut sum = 0.0f32;
+                for p in 0..n {
+                    sum += a[i * n + p] * b[p * k + j];
+                }
+                out[i * k + j] = sum;
+            }
+        }
+        out
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn cpu_conv2d(
+        input: &[f32],
+        weight: &[f32],
+        bias: Option<&[f32]>,
+        in_shape: (usize, usize, usize, usize),
+        out_channels: usize,
+        kernel: (usize, usize),
+        stride: (usize, usize),
+        padding: (usize, usize),
+    ) -> Vec<f32> {
+        let (n, c, h, w) = in_shape;
+        let (kh, kw) = kernel;
+        let (sh, sw) = stride;
+        let (ph, pw) = padding;
+        let oh = (h + 2 * ph - kh) / sh + 1;
+        let ow = (w + 2 * pw - kw) / sw + 1;
+
+        let mut out = vec![0.0f32; n * out_channels * oh * ow];
+        for ni in 0..n {
+            for oc in 0..out_channels {
+                let base_bias = bias.map(|b| b[oc]).unwrap_or(0.0);
+                for oy in 0..oh {
+                    for ox in 0..ow {
+                        let mut sum = base_bias;
+                        for ic in 0..c {
+                            for ky in 0..kh {
+                                let iy = oy * sh + ky;
+                                if iy < ph || iy - ph >= h {
+                                    continue;
+                                }
+                                let iy = iy - ph;
+                                for kx in 0..kw {
+                                    let ix = ox * sw + kx;
+                                    if ix < pw || ix - pw >= w {
+                                        continue;
+                                    }
+                                    let ix = ix - pw;
+                                    let in_idx = ((ni * c + ic) * h + iy) * w + ix;
+                                    let w_idx = ((oc * c + ic) * kh + ky) * kw + kx;
+                                    sum += input[in_idx] * weight[w_idx];
+                                }
+                            }
+                        }
+                        out[((ni * out_channels + oc) * oh + oy) * ow + ox] = sum;
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    // chunk0-1: cached pipelines/command queue must not change op results across
+    // repeated calls.
+    #[test]
+    fn repeated_calls_are_deterministic() {
+        let backend = MpsBackend::new().expect("MPS device required");
+        let a = vec![1.0, 2.0, 3.0, 4.0];
+        let b = vec![5.0, 6.0, 7.0, 8.0];
+
+        let first = Backend::add(&backend, &a, &b);
+        let second = Backend::add(&backend, &a, &b);
+        assert_eq!(first, second);
+    }
+
+    // chunk0-2: MPSMatrixMultiplication-backed matmul against a CPU reference,
+    // for a non-square shape.
+    #[test]
+    fn matmul_mps_matches_cpu_reference() {
+        let backend = MpsBackend::new().expect("MPS device required");
+        let (m, n, k) = (3, 4, 2);
+        let a: Vec<f32> = (0..m * n).map(|i| i as f32).collect();
+        let b: Vec<f32> = (0..n * k).map(|i| i as f32 * 0.5).collect();
+
+        let buffer_a = backend.create_buffer(&a).unwrap();
+        let buffer_b = backend.create_buffer(&b).unwrap();
+        let result_buffer = backend
+            .matmul_mps(&buffer_a, &buffer_b, m, n, k, false, false, 1.0, 0.0)
+            .unwrap();
+        let result_slice =
+            unsafe { std::slice::from_raw_parts(result_buffer.contents() as *const f32, m * k) };
+
+        assert_close(result_slice, &cpu_matmul(&a, &b, m, n, k), 1e-3);
+    }
+
+    // chunk0-3: the FP16 compute path must stay within tolerance of the FP32 reference.
+    #[test]
+    fn matmul_dtype_f16_within_tolerance() {
+        let backend = MpsBackend::new().expect("MPS device required");
+        let (m, n, k) = (4, 4, 4);
+        let a: Vec<f32> = (0..m * n).map(|i| i as f32 * 0.1).collect();
+        let b: Vec<f32> = (0..n * k).map(|i| i as f32 * 0.2).collect();
+
+        let f32_result = backend.matmul_dtype(&a, &b, m, n, k, MpsDataType::F32);
+        let f16_result = backend.matmul_dtype(&a, &b, m, n, k, MpsDataType::F16);
+
+        assert_close(&f16_result, &f32_result, 0.5);
+    }
+
+    // chunk0-4: the elementwise math ops and fused activation kernels against
+    // CPU references.
+    #[test]
+    fn elementwise_math_matches_cpu_reference() {
+        let backend = MpsBackend::new().expect("MPS device required");
+        let a = vec![1.0f32, 4.0, 9.0, 16.0];
+        let b = vec![2.0f32, 2.0, 3.0, 4.0];
+
+        let exp_expected: Vec<f32> = a.iter().map(|x| x.exp()).collect();
+        assert_close(&Backend::exp(&backend, &a), &exp_expected, 1e-2);
+
+        let sqrt_expected: Vec<f32> = a.iter().map(|x| x.sqrt()).collect();
+        assert_close(&Backend::sqrt(&backend, &a), &sqrt_expected, 1e-3);
+
+        let pow_expected: Vec<f32> = a.iter().map(|x| x.powf(2.0)).collect();
+        assert_close(&Backend::pow(&backend, &a, 2.0), &pow_expected, 1e-3);
+
+        let div_expected: Vec<f32> = a.iter().zip(&b).map(|(x, y)| x / y).collect();
+        assert_close(&Backend::div(&backend, &a, &b), &div_expected, 1e-3);
+    }
+
+    #[test]
+    fn activation_kernels_match_cpu_reference() {
+        let backend = MpsBackend::new().expect("MPS device required");
+        let a = vec![-2.0f32, -0.5, 0.0, 0.5, 2.0];
+
+        let relu_expected: Vec<f32> = a.iter().map(|&x| x.max(0.0)).collect();
+        assert_close(&backend.relu(&a), &relu_expected, 1e-6);
+
+        let silu_expected: Vec<f32> = a.iter().map(|&x| x / (1.0 + (-x).exp())).collect();
+        assert_close(&backend.silu(&a), &silu_expected, 1e-4);
+
+        let gelu_expected: Vec<f32> = a
+            .iter()
+            .map(|&x| 0.5 * x * (1.0 + (0.7978845608 * (x + 0.044715 * x.powi(3))).tanh()))
+            .collect();
+        assert_close(&backend.gelu(&a), &gelu_expected, 1e-4);
+
+        let row = vec![1.0f32, 2.0, 3.0];
+        let row_max = row.iter().cloned().fold(f32::MIN, f32::max);
+        let exps: Vec<f32> = row.iter().map(|x| (x - row_max).exp()).collect();
+        let row_sum: f32 = exps.iter().sum();
+        let softmax_expected: Vec<f32> = exps.iter().map(|e| e / row_sum).collect();
+        assert_close(&backend.softmax(&row, 1, 3), &softmax_expected, 1e-4);
+    }
+
+    // chunk0-5: the tiled kernel must match the naive kernel on a size that
+    // isn't a multiple of the tile size.
+    #[test]
+    fn matmul_tiled_matches_naive() {
+        let backend = MpsBackend::new().expect("MPS device required");
+        let (m, n, k) = (33, 17, 29);
+        let a: Vec<f32> = (0..m * n).map(|i| (i % 7) as f32).collect();
+        let b: Vec<f32> = (0..n * k).map(|i| (i % 5) as f32).collect();
+
+        let buffer_a = backend.create_buffer(&a).unwrap();
+        let buffer_b = backend.create_buffer(&b).unwrap();
+
+        let naive = backend.matmul(&buffer_a, &buffer_b, m, n, k).unwrap();
+        let tiled = backend.matmul_tiled(&buffer_a, &buffer_b, m, n, k).unwrap();
+
+        let naive_slice =
+            unsafe { std::slice::from_raw_parts(naive.contents() as *const f32, m * k) };
+        let tiled_slice =
+            unsafe { std::slice::from_raw_parts(tiled.contents() as *const f32, m * k) };
+
+        assert_close(naive_slice, tiled_slice, 1e-3);
+    }
+
+    // chunk0-6: dequantize-then-matmul against direct f32 matmul, for both formats.
+    #[test]
+    fn quantized_matmul_within_tolerance() {
+        let backend = MpsBackend::new().expect("MPS device required");
+        let (m, n, k) = (2, 40, 3); // in_features isn't a multiple of QBLOCK_SIZE
+        let a: Vec<f32> = (0..m * n).map(|i| (i % 11) as f32 * 0.1 - 0.5).collect();
+        let w: Vec<f32> = (0..k * n).map(|i| (i % 13) as f32 * 0.1 - 0.6).collect();
+
+        let mut w_transposed = vec![0.0f32; n * k];
+        for row in 0..k {
+            for col in 0..n {
+                w_transposed[col * k + row] = w[row * n + col];
+            }
+        }
+        let reference = cpu_matmul(&a, &w_transposed, m, n, k);
+
+        let q8 = QTensor::quantize_q8_0(&w, k, n);
+        assert_close(&backend.matmul_quantized(&a, &q8, m, n, k), &reference, 0.5);
+
+        let q4 = QTensor::quantize_q4_0(&w, k, n);
+        assert_close(&backend.matmul_quantized(&a, &q4, m, n, k), &reference, 1.5);
+    }
+
+    // chunk0-7: conv2d against a CPU reference for a small input with stride and padding.
+    #[test]
+    fn conv2d_matches_cpu_reference() {
+        let backend = MpsBackend::new().expect("MPS device required");
+        let in_shape = (1, 2, 5, 5);
+        let (n, c, h, w) = in_shape;
+        let out_channels = 3;
+        let kernel = (3, 3);
+        let stride = (2, 2);
+        let padding = (1, 1);
+
+        let input: Vec<f32> = (0..n * c * h * w).map(|i| (i % 7) as f32 * 0.1).collect();
+        let weight: Vec<f32> = (0..out_channels * c * kernel.0 * kernel.1)
+            .map(|i| (i % 5) as f32 * 0.1 - 0.2)
+            .collect();
+        let bias: Vec<f32> = (0..out_channels).map(|i| i as f32 * 0.1).collect();
+
+        let gpu_result = backend.conv2d(
+            &input,
+            &weight,
+            Some(&bias),
+            in_shape,
+            out_channels,
+            kernel,
+            stride,
+            padding,
+        );
+        let cpu_result = cpu_conv2d(
+            &input,
+            &weight,
+            Some(&bias),
+            in_shape,
+            out_channels,
+            kernel,
+            stride,
+            padding,
+        );
+
+        assert_close(&gpu_result, &cpu_result, 1e-3);
+    }
+
+    // chunk0-8: a profiled op on a much larger input shouldn't report less GPU
+    // time than the same op on a tiny one.
+    #[test]
+    fn profiled_matmul_timing_is_monotonic_in_size() {
+        let backend = MpsBackend::new().expect("MPS device required");
+
+        let small = vec![1.0f32; 8 * 8];
+        let small_buf = backend.create_buffer(&small).unwrap();
+        backend
+            .matmul_profiled(&small_buf, &small_buf, 8, 8, 8)
+            .unwrap();
+        let small_timing = backend.last_op_timing();
+
+        let large = vec![1.0f32; 256 * 256];
+        let large_buf = backend.create_buffer(&large).unwrap();
+        backend
+            .matmul_profiled(&large_buf, &large_buf, 256, 256, 256)
+            .unwrap();
+        let large_timing = backend.last_op_timing();
+
+        if let (Some(small_timing), Some(large_timing)) = (small_timing, large_timing) {
+            assert!(large_timing >= small_timing);
+        }
+    }
+
+    // chunk0-9: bias-style and per-row broadcasts, plus scalar max/min, against
+    // CPU references.
+    #[test]
+    fn broadcast_binary_matches_cpu_reference() {
+        let backend = MpsBackend::new().expect("MPS device required");
+        let a = vec![1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]; // 2x3
+
+        let bias = vec![10.0f32, 20.0, 30.0]; // 1x3
+        let result = backend
+            .broadcast_binary(&a, &[2, 3], &bias, &[1, 3], BroadcastOp::Add)
+            .unwrap();
+        assert_eq!(result, vec![11.0, 22.0, 33.0, 14.0, 25.0, 36.0]);
+
+        let col = vec![100.0f32, 200.0]; // 2x1
+        let result = backend
+            .broadcast_binary(&a, &[2, 3], &col, &[2, 1], BroadcastOp::Add)
+            .unwrap();
+        assert_eq!(result, vec![101.0, 102.0, 103.0, 204.0, 205.0, 206.0]);
+
+        let scalar = vec![3.5f32];
+        let max_result = backend
+            .broadcast_binary(&a, &[2, 3], &scalar, &[1], BroadcastOp::Max)
+            .unwrap();
+        assert_eq!(max_result, vec![3.5, 3.5, 3.5, 4.0, 5.0, 6.0]);
+
+        let min_result = backend
+            .broadcast_binary(&a, &[2, 3], &scalar, &[1], BroadcastOp::Min)
+            .unwrap();
+        assert_eq!(min_result, vec![1.0, 2.0, 3.0, 3.5, 3.5, 3.5]);
+    }
+}